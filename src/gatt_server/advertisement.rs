@@ -0,0 +1,149 @@
+//! Configurable BLE advertising: device name, advertised services, appearance,
+//! manufacturer data and the advertising parameters themselves.
+
+use crate::leaky_box_raw;
+use crate::utilities::BleUuid;
+use esp_idf_sys::{esp_ble_adv_data_t, esp_ble_adv_params_t};
+
+/// The payload of either the advertising packet or the scan response packet.
+///
+/// Both packets share the same 31-byte budget and the same set of fields;
+/// Bluedroid just happens to configure them through two separate calls.
+#[derive(Debug, Clone, Default)]
+pub struct AdvertisementPayload {
+    pub(crate) include_name: bool,
+    pub(crate) include_tx_power_level: bool,
+    pub(crate) appearance: u16,
+    pub(crate) service_uuids: Vec<BleUuid>,
+    pub(crate) manufacturer_data: Vec<u8>,
+}
+
+impl AdvertisementPayload {
+    /// Creates an empty payload: no name, no services, no manufacturer data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Includes the device name (set via [`Advertisement::new`]) in this payload.
+    pub fn include_name(mut self, include: bool) -> Self {
+        self.include_name = include;
+        self
+    }
+
+    /// Includes the radio's current TX power level in this payload.
+    pub fn include_tx_power_level(mut self, include: bool) -> Self {
+        self.include_tx_power_level = include;
+        self
+    }
+
+    /// Sets the GAP appearance value (see the Bluetooth Assigned Numbers document).
+    pub fn appearance(mut self, appearance: u16) -> Self {
+        self.appearance = appearance;
+        self
+    }
+
+    /// Advertises the given service UUIDs as "complete list of service UUIDs".
+    pub fn include_services(mut self, uuids: impl IntoIterator<Item = BleUuid>) -> Self {
+        self.service_uuids = uuids.into_iter().collect();
+        self
+    }
+
+    /// Sets manufacturer-specific data (company identifier plus payload).
+    pub fn manufacturer_data(mut self, data: Vec<u8>) -> Self {
+        self.manufacturer_data = data;
+        self
+    }
+
+    pub(crate) fn to_esp_adv_data(&self, device_name_set: bool, set_scan_rsp: bool) -> esp_ble_adv_data_t {
+        esp_ble_adv_data_t {
+            set_scan_rsp,
+            include_name: self.include_name && device_name_set,
+            include_txpower: self.include_tx_power_level,
+            appearance: self.appearance as _,
+            manufacturer_len: self.manufacturer_data.len() as u16,
+            p_manufacturer_data: if self.manufacturer_data.is_empty() {
+                std::ptr::null_mut()
+            } else {
+                leaky_box_raw!(self.manufacturer_data.as_slice()) as *mut u8
+            },
+            service_uuid_len: self
+                .service_uuids
+                .iter()
+                .map(|uuid| match uuid {
+                    BleUuid::Uuid16(_) => 2,
+                    BleUuid::Uuid32(_) => 4,
+                    BleUuid::Uuid128(_) => 16,
+                })
+                .sum::<usize>() as u16,
+            p_service_uuid: if self.service_uuids.is_empty() {
+                std::ptr::null_mut()
+            } else {
+                leaky_box_raw!(self
+                    .service_uuids
+                    .iter()
+                    .flat_map(|uuid| match uuid {
+                        BleUuid::Uuid16(uuid) => uuid.to_le_bytes().to_vec(),
+                        BleUuid::Uuid32(uuid) => uuid.to_le_bytes().to_vec(),
+                        BleUuid::Uuid128(bytes) => bytes.to_vec(),
+                    })
+                    .collect::<Vec<u8>>()
+                    .as_slice()) as *mut u8
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// The full advertising configuration for a [`GattServer`](crate::gatt_server::GattServer):
+/// the device name, the advertising and scan-response payloads, and the
+/// advertising parameters (interval, channel map, filter policy, ...).
+#[derive(Debug, Clone)]
+pub struct Advertisement {
+    pub(crate) device_name: String,
+    pub(crate) advertisement_data: AdvertisementPayload,
+    pub(crate) scan_response_data: Option<AdvertisementPayload>,
+    pub(crate) parameters: esp_ble_adv_params_t,
+}
+
+impl Advertisement {
+    /// Creates a new [`Advertisement`] with the given device name and default
+    /// (undirected, general-discoverable) advertising parameters.
+    ///
+    /// Defaults the advertising interval to 0x20-0x40 (20-40 ms): bindgen's
+    /// all-zero `Default` for `esp_ble_adv_params_t` leaves `adv_int_min`/`_max`
+    /// at 0, which is below Bluedroid's minimum of 0x0020 and would make
+    /// [`start_advertising`](crate::gatt_server::GattServer::start_advertising)
+    /// fail unless [`interval`](Self::interval) is called explicitly.
+    pub fn new(device_name: &str) -> Self {
+        Advertisement {
+            device_name: device_name.to_string(),
+            advertisement_data: AdvertisementPayload::new().include_name(true),
+            scan_response_data: None,
+            parameters: esp_ble_adv_params_t {
+                adv_int_min: 0x20,
+                adv_int_max: 0x40,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Overrides the advertising packet payload (defaults to just the device name).
+    pub fn advertisement_data(mut self, data: AdvertisementPayload) -> Self {
+        self.advertisement_data = data;
+        self
+    }
+
+    /// Sets a separate scan-response packet payload, sent when a client performs
+    /// an active scan. Defaults to a copy of the advertising packet payload.
+    pub fn scan_response_data(mut self, data: AdvertisementPayload) -> Self {
+        self.scan_response_data = Some(data);
+        self
+    }
+
+    /// Sets the advertising interval, in units of 0.625 ms.
+    pub fn interval(mut self, min: u32, max: u32) -> Self {
+        self.parameters.adv_int_min = min;
+        self.parameters.adv_int_max = max;
+        self
+    }
+}
@@ -0,0 +1,189 @@
+//! A fluent, typestate-ish builder API for assembling a [`GattServer`] out of
+//! profiles, services, characteristics and descriptors in one chained expression.
+//!
+//! ```ignore
+//! let server = GattServer::builder()
+//!     .profile(0, "Main profile")
+//!     .service(BleUuid::from_uuid16(0x00ff), "Custom service")
+//!     .characteristic(
+//!         BleUuid::from_uuid16(0xff01),
+//!         "Custom characteristic",
+//!         AttributePermissions::ReadWrite,
+//!         CharacteristicProperties::READ | CharacteristicProperties::WRITE,
+//!     )
+//!     .value(vec![0x01])
+//!     .finish()?
+//!     .finish()
+//!     .finish()
+//!     .finish();
+//! ```
+
+use crate::gatt_server::characteristic::Characteristic;
+use crate::gatt_server::descriptor::Descriptor;
+use crate::gatt_server::profile::Profile;
+use crate::gatt_server::service::Service;
+use crate::gatt_server::GattServer;
+use crate::utilities::{AttributePermissions, BleUuid, CharacteristicProperties};
+use std::fmt::{Display, Formatter};
+
+/// An error produced while assembling a [`GattServer`] through its builder API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// The characteristic's [`AttributeControl`](crate::utilities::AttributeControl)
+    /// is `AutomaticResponse`, but no value was ever set on it.
+    AutomaticResponseWithoutValue,
+    /// The characteristic's properties include `NOTIFY` or `INDICATE`, but it has
+    /// no Client Characteristic Configuration Descriptor (UUID 0x2902) attached.
+    NotifyWithoutCccd,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::AutomaticResponseWithoutValue => write!(
+                f,
+                "characteristic uses AttributeControl::AutomaticResponse but has no value"
+            ),
+            BuildError::NotifyWithoutCccd => write!(
+                f,
+                "characteristic has NOTIFY or INDICATE properties but no Client \
+                 Characteristic Configuration Descriptor (0x2902)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl GattServer {
+    /// Starts building a [`GattServer`] through its fluent builder API.
+    pub fn builder() -> GattServerBuilder {
+        GattServerBuilder {
+            server: GattServer::new(),
+        }
+    }
+}
+
+/// Builds a [`GattServer`] one [`Profile`] at a time.
+pub struct GattServerBuilder {
+    server: GattServer,
+}
+
+impl GattServerBuilder {
+    /// Starts building a [`Profile`] with the given application identifier.
+    pub fn profile(self, identifier: u16, name: &str) -> ProfileBuilder {
+        ProfileBuilder {
+            server: self,
+            profile: Profile::new(name, identifier),
+        }
+    }
+
+    /// Finishes building, returning the assembled [`GattServer`].
+    pub fn finish(self) -> GattServer {
+        self.server
+    }
+}
+
+/// Builds a [`Profile`] one [`Service`] at a time.
+pub struct ProfileBuilder {
+    server: GattServerBuilder,
+    profile: Profile,
+}
+
+impl ProfileBuilder {
+    /// Starts building a primary [`Service`] with the given UUID.
+    pub fn service(self, uuid: BleUuid, name: &str) -> ServiceBuilder {
+        ServiceBuilder {
+            profile: self,
+            service: Service::new(name, uuid),
+        }
+    }
+
+    /// Finishes this profile, adding it to the server, and returns the server builder.
+    pub fn finish(mut self) -> GattServerBuilder {
+        self.server.server.profile(self.profile);
+        self.server
+    }
+}
+
+/// Builds a [`Service`] one [`Characteristic`] at a time.
+pub struct ServiceBuilder {
+    profile: ProfileBuilder,
+    service: Service,
+}
+
+impl ServiceBuilder {
+    /// Starts building a [`Characteristic`] with the given UUID, permissions and properties.
+    pub fn characteristic(
+        self,
+        uuid: BleUuid,
+        name: &str,
+        permissions: AttributePermissions,
+        properties: CharacteristicProperties,
+    ) -> CharacteristicBuilder {
+        CharacteristicBuilder {
+            service: self,
+            characteristic: Characteristic::new(name, uuid, permissions, properties),
+        }
+    }
+
+    /// Finishes this service, adding it to the profile, and returns the profile builder.
+    pub fn finish(mut self) -> ProfileBuilder {
+        self.profile.profile.add_service(&mut self.service);
+        self.profile
+    }
+}
+
+/// Builds a [`Characteristic`], validating it against Bluedroid's constraints
+/// once [`finish`](Self::finish) is called.
+pub struct CharacteristicBuilder {
+    service: ServiceBuilder,
+    characteristic: Characteristic,
+}
+
+impl CharacteristicBuilder {
+    /// Sets the characteristic's initial value.
+    pub fn value(mut self, value: Vec<u8>) -> Self {
+        self.characteristic.value = value;
+        self
+    }
+
+    /// Registers a callback to compute this characteristic's value on demand. See
+    /// [`Characteristic::on_read`].
+    pub fn on_read(
+        mut self,
+        callback: impl FnMut(&mut Characteristic) -> Vec<u8> + Send + 'static,
+    ) -> Self {
+        self.characteristic.on_read(callback);
+        self
+    }
+
+    /// Registers a callback invoked on every client write. See [`Characteristic::on_write`].
+    pub fn on_write(
+        mut self,
+        callback: impl FnMut(Vec<u8>, &mut Characteristic) + Send + 'static,
+    ) -> Self {
+        self.characteristic.on_write(callback);
+        self
+    }
+
+    /// Adds a [`Descriptor`] to the characteristic, e.g. its CCCD.
+    pub fn descriptor(mut self, descriptor: &mut Descriptor) -> Self {
+        self.characteristic.add_descriptor(descriptor);
+        self
+    }
+
+    /// Validates and finishes this characteristic, adding it to the service and
+    /// returning the service builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BuildError::AutomaticResponseWithoutValue`] or
+    /// [`BuildError::NotifyWithoutCccd`] if the characteristic is misconfigured;
+    /// see [`Characteristic::validate`](crate::gatt_server::characteristic::Characteristic).
+    pub fn finish(mut self) -> Result<ServiceBuilder, BuildError> {
+        self.characteristic.validate()?;
+        self.service.service.add_characteristic(&mut self.characteristic);
+        Ok(self.service)
+    }
+}
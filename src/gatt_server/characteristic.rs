@@ -4,21 +4,66 @@ use crate::utilities::CharacteristicProperties;
 use crate::{gatt_server::descriptor::Descriptor, leaky_box_raw, utilities::BleUuid};
 use esp_idf_sys::esp_attr_control_t;
 use esp_idf_sys::esp_attr_value_t;
-use esp_idf_sys::{esp_ble_gatts_add_char, esp_nofail};
-use log::info;
+use esp_idf_sys::{esp_ble_gatts_add_char, esp_ble_gatts_send_indicate, esp_gatt_if_t, esp_nofail};
+use log::{info, warn};
+use std::collections::HashMap;
 use std::fmt::Formatter;
+use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone)]
+/// Whether a connected client has enabled notifications, indications, or
+/// neither, via the 0x2902 Client Characteristic Configuration Descriptor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct CccdSubscription {
+    pub notify: bool,
+    pub indicate: bool,
+}
+
+impl CccdSubscription {
+    /// Parses the two-byte little-endian value written to a CCCD.
+    pub fn from_value(value: &[u8]) -> Self {
+        let bits = value.first().copied().unwrap_or(0);
+        CccdSubscription {
+            notify: bits & 0b01 != 0,
+            indicate: bits & 0b10 != 0,
+        }
+    }
+}
+
+/// A user-supplied callback invoked on every `ESP_GATTS_READ_EVT` for a
+/// characteristic whose [`AttributeControl`] is [`AttributeControl::ResponseByApp`].
+///
+/// Returns the characteristic's full, current value; the read handler takes care
+/// of slicing it down to whatever the client actually asked for.
+type OnReadCallback = dyn FnMut(&mut Characteristic) -> Vec<u8> + Send;
+
+/// A user-supplied callback invoked whenever a client writes to a characteristic.
+///
+/// Runs after the new value has already been stored in
+/// [`value`](Characteristic::value), receiving the raw written bytes again for
+/// convenience.
+type OnWriteCallback = dyn FnMut(Vec<u8>, &mut Characteristic) + Send;
+
+#[derive(Clone)]
 pub struct Characteristic {
     name: Option<String>,
     pub(crate) uuid: BleUuid,
-    value: Vec<u8>,
+    pub(crate) value: Vec<u8>,
     pub(crate) descriptors: Vec<Descriptor>,
     pub(crate) attribute_handle: Option<u16>,
     service_handle: Option<u16>,
     permissions: AttributePermissions,
-    properties: CharacteristicProperties,
-    control: AttributeControl,
+    pub(crate) properties: CharacteristicProperties,
+    pub(crate) control: AttributeControl,
+    on_read: Option<Arc<Mutex<OnReadCallback>>>,
+    on_write: Option<Arc<Mutex<OnWriteCallback>>>,
+    /// Fragments accumulated from `ESP_GATTS_WRITE_EVT`s with `is_prep` set, to be
+    /// committed or discarded once `ESP_GATTS_EXEC_WRITE_EVT` arrives.
+    prepare_write_buffer: Vec<u8>,
+    /// The GATT interface this characteristic was registered on, used to send
+    /// notifications/indications. Set once the characteristic is registered.
+    pub(crate) interface: Option<esp_gatt_if_t>,
+    /// Per-connection notify/indicate subscription state, keyed by `conn_id`.
+    pub(crate) subscriptions: HashMap<u16, CccdSubscription>,
 }
 
 impl Characteristic {
@@ -39,6 +84,11 @@ impl Characteristic {
             permissions,
             properties,
             control: AttributeControl::ResponseByApp,
+            on_read: None,
+            on_write: None,
+            prepare_write_buffer: Vec::new(),
+            interface: None,
+            subscriptions: HashMap::new(),
         }
     }
 
@@ -48,6 +98,179 @@ impl Characteristic {
         self
     }
 
+    /// Registers a callback to compute this characteristic's value on demand,
+    /// instead of always answering reads with the last value set on it.
+    ///
+    /// Only takes effect while the characteristic's [`AttributeControl`] is
+    /// [`AttributeControl::ResponseByApp`] (the default).
+    pub fn on_read(
+        &mut self,
+        callback: impl FnMut(&mut Characteristic) -> Vec<u8> + Send + 'static,
+    ) -> &mut Self {
+        self.on_read = Some(Arc::new(Mutex::new(callback)));
+        self
+    }
+
+    /// Returns the value that should be used to answer a read of this characteristic,
+    /// invoking the [`on_read`](Self::on_read) callback if one is registered, or
+    /// falling back to the statically stored [`value`](Self::value) otherwise.
+    pub(crate) fn read_value(&mut self) -> Vec<u8> {
+        match self.on_read.clone() {
+            Some(callback) => {
+                let mut callback = callback.lock().unwrap();
+                (callback)(self)
+            }
+            None => self.value.clone(),
+        }
+    }
+
+    /// Registers a callback invoked whenever a client writes to this characteristic.
+    ///
+    /// By the time the callback runs, the new bytes have already been stored in
+    /// [`value`](Self::value); the callback is also handed the raw written bytes
+    /// directly, for convenience.
+    pub fn on_write(
+        &mut self,
+        callback: impl FnMut(Vec<u8>, &mut Characteristic) + Send + 'static,
+    ) -> &mut Self {
+        self.on_write = Some(Arc::new(Mutex::new(callback)));
+        self
+    }
+
+    /// Stores a fully-received value and invokes the
+    /// [`on_write`](Self::on_write) callback, if any.
+    pub(crate) fn write_value(&mut self, value: Vec<u8>) {
+        self.value = value.clone();
+
+        if let Some(callback) = self.on_write.clone() {
+            let mut callback = callback.lock().unwrap();
+            (callback)(value, self);
+        }
+    }
+
+    /// Buffers a fragment of a long write (an `ESP_GATTS_WRITE_EVT` with `is_prep`
+    /// set), to be committed or discarded once `ESP_GATTS_EXEC_WRITE_EVT` arrives.
+    pub(crate) fn buffer_prepared_write(&mut self, offset: usize, fragment: &[u8]) {
+        if self.prepare_write_buffer.len() < offset + fragment.len() {
+            self.prepare_write_buffer.resize(offset + fragment.len(), 0);
+        }
+        self.prepare_write_buffer[offset..offset + fragment.len()].copy_from_slice(fragment);
+    }
+
+    /// Commits a buffered long write as this characteristic's new value.
+    pub(crate) fn commit_prepared_write(&mut self) {
+        if !self.prepare_write_buffer.is_empty() {
+            let value = std::mem::take(&mut self.prepare_write_buffer);
+            self.write_value(value);
+        }
+    }
+
+    /// Discards a buffered long write without applying it.
+    pub(crate) fn cancel_prepared_write(&mut self) {
+        self.prepare_write_buffer.clear();
+    }
+
+    /// Sets this characteristic's value and notifies or indicates every
+    /// connected client that has subscribed to it via its CCCD.
+    pub fn set_value(&mut self, value: Vec<u8>) {
+        self.value = value;
+
+        if self.subscriptions.values().all(|s| !s.notify && !s.indicate) {
+            return;
+        }
+
+        let interface = match self.interface {
+            Some(interface) => interface,
+            None => {
+                warn!(
+                    "Cannot notify/indicate {}: not yet registered with the stack.",
+                    self
+                );
+                return;
+            }
+        };
+
+        let attribute_handle = match self.attribute_handle {
+            Some(handle) => handle,
+            None => return,
+        };
+
+        for (&conn_id, subscription) in self.subscriptions.iter() {
+            if !subscription.notify && !subscription.indicate {
+                continue;
+            }
+
+            info!(
+                "Sending {} of {} to connection {}.",
+                if subscription.indicate {
+                    "indication"
+                } else {
+                    "notification"
+                },
+                self,
+                conn_id
+            );
+
+            unsafe {
+                // `esp_ble_gatts_send_indicate` copies the value into its own buffer
+                // before returning, so there is no need to leak an allocation here
+                // (unlike the attribute values handed to Bluedroid at registration,
+                // which it keeps a long-lived pointer to).
+                esp_nofail!(esp_ble_gatts_send_indicate(
+                    interface,
+                    conn_id,
+                    attribute_handle,
+                    self.value.len() as u16,
+                    self.value.as_mut_ptr(),
+                    subscription.indicate,
+                ));
+            }
+        }
+    }
+
+    /// Updates the subscription state for `conn_id` from a write to this
+    /// characteristic's Client Characteristic Configuration Descriptor.
+    pub(crate) fn set_subscription(&mut self, conn_id: u16, value: &[u8]) {
+        let subscription = CccdSubscription::from_value(value);
+        info!(
+            "Connection {} set notify={}, indicate={} on {}.",
+            conn_id, subscription.notify, subscription.indicate, self
+        );
+        self.subscriptions.insert(conn_id, subscription);
+    }
+
+    /// Drops any subscription state held for a now-disconnected client.
+    pub(crate) fn clear_subscription(&mut self, conn_id: u16) {
+        self.subscriptions.remove(&conn_id);
+    }
+
+    /// Checks this characteristic against Bluedroid's constraints.
+    ///
+    /// The builder API (see [`crate::gatt_server::builder`]) calls this before a
+    /// characteristic is ever attached to a [`Service`](crate::gatt_server::service::Service),
+    /// so a misconfigured characteristic is rejected before registration rather
+    /// than causing a runtime panic.
+    pub(crate) fn validate(&self) -> Result<(), crate::gatt_server::builder::BuildError> {
+        use crate::gatt_server::builder::BuildError;
+
+        if self.control == AttributeControl::AutomaticResponse && self.value.is_empty() {
+            return Err(BuildError::AutomaticResponseWithoutValue);
+        }
+
+        let notifies = self.properties.contains(CharacteristicProperties::NOTIFY)
+            || self.properties.contains(CharacteristicProperties::INDICATE);
+        let has_cccd = self
+            .descriptors
+            .iter()
+            .any(|descriptor| descriptor.uuid == BleUuid::CLIENT_CHARACTERISTIC_CONFIGURATION);
+
+        if notifies && !has_cccd {
+            return Err(BuildError::NotifyWithoutCccd);
+        }
+
+        Ok(())
+    }
+
     /// Registers the [`Characteristic`] at the given service handle.
     pub(crate) fn register_self(&mut self, service_handle: u16) {
         info!(
@@ -56,10 +279,14 @@ impl Characteristic {
         );
         self.service_handle = Some(service_handle);
 
-        if self.control == AttributeControl::AutomaticResponse && self.value.len() == 0 {
-            panic!("Cannot set attribute control to Auto without a value.");
+        // Always enforced, not just in debug builds: a `Characteristic` built
+        // directly (bypassing the builder's `validate` call) must not silently
+        // reach Bluedroid in a broken state, e.g. an automatic-response
+        // characteristic with `attr_max_len == 0`.
+        if let Err(error) = self.validate() {
+            panic!("{self} was registered without being validated first: {error}");
         }
-        
+
         unsafe {
             esp_nofail!(esp_ble_gatts_add_char(
                 service_handle,
@@ -111,4 +338,25 @@ impl std::fmt::Display for Characteristic {
             self.uuid
         )
     }
+}
+
+impl std::fmt::Debug for Characteristic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Characteristic")
+            .field("name", &self.name)
+            .field("uuid", &self.uuid)
+            .field("value", &self.value)
+            .field("descriptors", &self.descriptors)
+            .field("attribute_handle", &self.attribute_handle)
+            .field("service_handle", &self.service_handle)
+            .field("permissions", &self.permissions)
+            .field("properties", &self.properties)
+            .field("control", &self.control)
+            .field("on_read", &self.on_read.as_ref().map(|_| "Fn"))
+            .field("on_write", &self.on_write.as_ref().map(|_| "Fn"))
+            .field("prepare_write_buffer", &self.prepare_write_buffer)
+            .field("interface", &self.interface)
+            .field("subscriptions", &self.subscriptions)
+            .finish()
+    }
 }
\ No newline at end of file
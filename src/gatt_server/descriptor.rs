@@ -0,0 +1,67 @@
+use crate::utilities::{AttributeControl, AttributePermissions, BleUuid};
+use crate::leaky_box_raw;
+use esp_idf_sys::{esp_attr_value_t, esp_ble_gatts_add_char_descr, esp_nofail};
+use log::info;
+use std::fmt::Formatter;
+
+#[derive(Debug, Clone)]
+pub struct Descriptor {
+    name: Option<String>,
+    pub(crate) uuid: BleUuid,
+    value: Vec<u8>,
+    pub(crate) attribute_handle: Option<u16>,
+    service_handle: Option<u16>,
+    permissions: AttributePermissions,
+    control: AttributeControl,
+}
+
+impl Descriptor {
+    /// Creates a new [`Descriptor`].
+    pub fn new(name: &str, uuid: BleUuid, permissions: AttributePermissions) -> Descriptor {
+        Descriptor {
+            name: Some(String::from(name)),
+            uuid,
+            value: Vec::new(),
+            attribute_handle: None,
+            service_handle: None,
+            permissions,
+            control: AttributeControl::ResponseByApp,
+        }
+    }
+
+    /// Registers the [`Descriptor`] at the given service handle.
+    pub(crate) fn register_self(&mut self, service_handle: u16) {
+        info!(
+            "Registering {} into service at handle 0x{:04x}.",
+            self, service_handle
+        );
+        self.service_handle = Some(service_handle);
+
+        unsafe {
+            esp_nofail!(esp_ble_gatts_add_char_descr(
+                service_handle,
+                leaky_box_raw!(self.uuid.into()),
+                self.permissions.into(),
+                leaky_box_raw!(esp_attr_value_t {
+                    attr_max_len: self.value.len() as u16,
+                    attr_len: self.value.len() as u16,
+                    attr_value: leaky_box_raw!(self.value.as_slice()) as *mut u8,
+                }),
+                &mut self.control.into()
+            ));
+        }
+    }
+}
+
+impl std::fmt::Display for Descriptor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({})",
+            self.name
+                .clone()
+                .unwrap_or_else(|| "Unnamed descriptor".to_string()),
+            self.uuid
+        )
+    }
+}
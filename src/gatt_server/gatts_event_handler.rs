@@ -1,14 +1,17 @@
 use crate::{gatt_server::GattServer, leaky_box_raw, utilities::BleUuid};
 use esp_idf_sys::{
-    esp_ble_gap_config_adv_data, esp_ble_gap_set_device_name, esp_ble_gap_start_advertising,
-    esp_ble_gatts_cb_param_t, esp_ble_gatts_send_response, esp_ble_gatts_start_service,
+    esp_ble_gap_config_adv_data, esp_ble_gap_set_device_name, esp_ble_gatts_cb_param_t,
+    esp_ble_gatts_send_response, esp_ble_gatts_start_service,
     esp_bt_status_t_ESP_BT_STATUS_SUCCESS, esp_gatt_if_t, esp_gatt_status_t_ESP_GATT_OK,
     esp_gatts_cb_event_t, esp_gatts_cb_event_t_ESP_GATTS_ADD_CHAR_DESCR_EVT,
-    esp_gatts_cb_event_t_ESP_GATTS_ADD_CHAR_EVT, esp_gatts_cb_event_t_ESP_GATTS_CANCEL_OPEN_EVT,
+    esp_gatts_cb_event_t_ESP_GATTS_ADD_CHAR_EVT, esp_gatts_cb_event_t_ESP_GATTS_ADD_INCL_SRVC_EVT,
+    esp_gatts_cb_event_t_ESP_GATTS_CANCEL_OPEN_EVT,
     esp_gatts_cb_event_t_ESP_GATTS_CONNECT_EVT, esp_gatts_cb_event_t_ESP_GATTS_CREATE_EVT,
-    esp_gatts_cb_event_t_ESP_GATTS_DISCONNECT_EVT, esp_gatts_cb_event_t_ESP_GATTS_MTU_EVT,
-    esp_gatts_cb_event_t_ESP_GATTS_READ_EVT, esp_gatts_cb_event_t_ESP_GATTS_REG_EVT,
-    esp_gatts_cb_event_t_ESP_GATTS_START_EVT, esp_nofail, esp_gatt_rsp_t, esp_attr_value_t, esp_gatt_value_t, esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE,
+    esp_gatts_cb_event_t_ESP_GATTS_DISCONNECT_EVT, esp_gatts_cb_event_t_ESP_GATTS_EXEC_WRITE_EVT,
+    esp_gatts_cb_event_t_ESP_GATTS_MTU_EVT, esp_gatts_cb_event_t_ESP_GATTS_READ_EVT,
+    esp_gatts_cb_event_t_ESP_GATTS_REG_EVT, esp_gatts_cb_event_t_ESP_GATTS_START_EVT,
+    esp_gatts_cb_event_t_ESP_GATTS_WRITE_EVT, esp_nofail, esp_gatt_rsp_t, esp_attr_value_t,
+    esp_gatt_value_t, esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE, ESP_GATT_PREP_WRITE_EXEC,
 };
 use log::{debug, info, warn};
 
@@ -30,6 +33,8 @@ impl GattServer {
                 let param = unsafe { (*param).connect };
                 info!("GATT client {:02X?} connected.", param.remote_bda.to_vec());
 
+                self.connections.push(param.conn_id);
+
                 // Do not pass this event to the profile handlers.
                 return;
             }
@@ -40,9 +45,14 @@ impl GattServer {
                     param.remote_bda.to_vec()
                 );
 
-                unsafe {
-                    esp_ble_gap_start_advertising(leaky_box_raw!(self.advertisement_parameters));
-                }
+                self.connections.retain(|&conn_id| conn_id != param.conn_id);
+                self.profiles
+                    .iter_mut()
+                    .flat_map(|profile| profile.services.iter_mut())
+                    .flat_map(|service| service.characteristics.iter_mut())
+                    .for_each(|characteristic| characteristic.clear_subscription(param.conn_id));
+
+                self.start_advertising();
 
                 // Do not pass this event to the profile handlers.
                 return;
@@ -50,6 +60,7 @@ impl GattServer {
             esp_gatts_cb_event_t_ESP_GATTS_MTU_EVT => {
                 let param = unsafe { (*param).mtu };
                 info!("MTU changed to {}.", param.mtu);
+                self.mtu = param.mtu;
 
                 // Do not pass this event to the profile handlers.
                 return;
@@ -68,26 +79,54 @@ impl GattServer {
                     profile.interface = Some(gatts_if);
 
                     if !self.name_set {
+                        let device_name = self
+                            .advertisement
+                            .as_ref()
+                            .map(|advertisement| advertisement.device_name.clone())
+                            .unwrap_or_else(|| "ESP32-GATT-Server".to_string());
+
                         unsafe {
                             esp_nofail!(esp_ble_gap_set_device_name(
-                                // TODO: Update name.
-                                b"ESP32-GATT-Server\0".as_ptr() as *const _,
+                                leaky_box_raw!(format!("{}\0", device_name)) as *const _
                             ));
 
                             self.name_set = true;
 
-                            // Advertisement data.
-                            esp_nofail!(esp_ble_gap_config_adv_data(leaky_box_raw!(
-                                self.advertisement_data
-                            )));
+                            match &self.advertisement {
+                                Some(advertisement) => {
+                                    esp_nofail!(esp_ble_gap_config_adv_data(leaky_box_raw!(
+                                        advertisement
+                                            .advertisement_data
+                                            .to_esp_adv_data(self.name_set, false)
+                                    )));
 
-                            // Scan response data.
-                            esp_nofail!(esp_ble_gap_config_adv_data(leaky_box_raw!(
-                                esp_idf_sys::esp_ble_adv_data_t {
-                                    set_scan_rsp: true,
-                                    ..self.advertisement_data
+                                    let scan_response_data = advertisement
+                                        .scan_response_data
+                                        .as_ref()
+                                        .unwrap_or(&advertisement.advertisement_data);
+                                    esp_nofail!(esp_ble_gap_config_adv_data(leaky_box_raw!(
+                                        scan_response_data.to_esp_adv_data(self.name_set, true)
+                                    )));
+                                }
+                                None => {
+                                    // No advertisement was configured: fall back to the
+                                    // previous hard-coded behaviour of advertising just
+                                    // the device name, with an identical scan response.
+                                    let advertisement_data = esp_idf_sys::esp_ble_adv_data_t {
+                                        include_name: true,
+                                        ..Default::default()
+                                    };
+                                    esp_nofail!(esp_ble_gap_config_adv_data(leaky_box_raw!(
+                                        advertisement_data
+                                    )));
+                                    esp_nofail!(esp_ble_gap_config_adv_data(leaky_box_raw!(
+                                        esp_idf_sys::esp_ble_adv_data_t {
+                                            set_scan_rsp: true,
+                                            ..advertisement_data
+                                        }
+                                    )));
                                 }
-                            )));
+                            }
                         }
                     }
                 }
@@ -95,10 +134,11 @@ impl GattServer {
             _ => {}
         }
 
+        let mtu = self.mtu;
         self.profiles.iter_mut().for_each(|profile| {
             if profile.interface == Some(gatts_if) {
                 debug!("Handling event {} on profile {}.", event, profile);
-                profile.gatts_event_handler(event, gatts_if, param)
+                profile.gatts_event_handler(event, gatts_if, param, mtu)
             }
         });
     }
@@ -111,6 +151,7 @@ impl Profile {
         event: esp_gatts_cb_event_t,
         gatts_if: esp_gatt_if_t,
         param: *mut esp_ble_gatts_cb_param_t,
+        mtu: u16,
     ) {
         #[allow(non_upper_case_globals)]
         match event {
@@ -132,23 +173,35 @@ impl Profile {
             esp_gatts_cb_event_t_ESP_GATTS_CREATE_EVT => {
                 let param = unsafe { (*param).create };
 
-                let service = self
+                let index = self
                     .services
-                    .iter_mut()
-                    .find(|service| service.uuid == BleUuid::from(param.service_id.id))
+                    .iter()
+                    .position(|service| service.uuid == BleUuid::from(param.service_id.id))
                     .expect("Cannot find service described by received handle.");
 
-                service.handle = Some(param.service_handle);
+                self.services[index].handle = Some(param.service_handle);
 
                 if param.status != esp_gatt_status_t_ESP_GATT_OK {
                     warn!("GATT service registration failed.");
                 } else {
                     info!(
                         "GATT service {} registered on handle 0x{:04x}.",
-                        service,
-                        service.handle.unwrap()
+                        self.services[index],
+                        self.services[index].handle.unwrap()
                     );
 
+                    // Snapshot every already-registered sibling's handle by UUID
+                    // before taking a mutable borrow of just this one service.
+                    let sibling_handles: std::collections::HashMap<BleUuid, u16> = self
+                        .services
+                        .iter()
+                        .filter_map(|service| service.handle.map(|handle| (service.uuid, handle)))
+                        .collect();
+
+                    let service = &mut self.services[index];
+
+                    service.register_included_services(&sibling_handles);
+
                     unsafe {
                         esp_nofail!(esp_ble_gatts_start_service(service.handle.unwrap()));
                     }
@@ -156,6 +209,29 @@ impl Profile {
                     service.register_characteristics();
                 }
             }
+            esp_gatts_cb_event_t_ESP_GATTS_ADD_INCL_SRVC_EVT => {
+                let param = unsafe { (*param).add_incl_srvc };
+
+                let service = self
+                    .services
+                    .iter_mut()
+                    .find(|service| service.handle == Some(param.service_handle));
+
+                if param.status != esp_gatt_status_t_ESP_GATT_OK {
+                    warn!("Failed to add included service.");
+                } else if let Some(service) = service {
+                    info!(
+                        "Included service registered at attribute handle 0x{:04x} in {}.",
+                        param.attr_handle, service
+                    );
+                    service.included_service_handles.push(param.attr_handle);
+                } else {
+                    warn!(
+                        "Received ADD_INCL_SRVC_EVT for unknown service handle 0x{:04x}.",
+                        param.service_handle
+                    );
+                }
+            }
             esp_gatts_cb_event_t_ESP_GATTS_START_EVT => {
                 let param = unsafe { (*param).start };
 
@@ -189,6 +265,7 @@ impl Profile {
                         characteristic, param.attr_handle
                     );
                     characteristic.attribute_handle = Some(param.attr_handle);
+                    characteristic.interface = Some(gatts_if);
                     characteristic.register_descriptors();
                 }
             }
@@ -220,22 +297,43 @@ impl Profile {
                     for characteristic in service.characteristics.iter_mut() {
                         if characteristic.attribute_handle == Some(param.handle) {
                             info!("Received read event for characteristic {}.", characteristic);
+
+                            let full_value = characteristic.read_value();
+                            let offset = param.offset as usize;
+
+                            // The response must fit in a single ATT_READ_RSP PDU: one byte
+                            // of opcode overhead, the rest is payload.
+                            let max_len = mtu.saturating_sub(1) as usize;
+
+                            let chunk: Vec<u8> = if offset >= full_value.len() {
+                                Vec::new()
+                            } else {
+                                full_value[offset..]
+                                    .iter()
+                                    .take(max_len)
+                                    .copied()
+                                    .collect()
+                            };
+
+                            let mut value = [0u8; 600];
+                            value[..chunk.len()].copy_from_slice(&chunk);
+
                             unsafe {
                                 esp_nofail!(esp_ble_gatts_send_response(
-                                gatts_if,
-                                param.conn_id,
-                                param.trans_id,
-                                esp_gatt_status_t_ESP_GATT_OK,
-                                leaky_box_raw!(esp_gatt_rsp_t {
-                                    attr_value: esp_gatt_value_t {
-                                        auth_req: 0,
-                                        handle: param.handle,
-                                        len: 1,
-                                        offset: 1,
-                                        value: [0; 600]
-                                    },
-                                })
-                            ));
+                                    gatts_if,
+                                    param.conn_id,
+                                    param.trans_id,
+                                    esp_gatt_status_t_ESP_GATT_OK,
+                                    leaky_box_raw!(esp_gatt_rsp_t {
+                                        attr_value: esp_gatt_value_t {
+                                            auth_req: esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE as _,
+                                            handle: param.handle,
+                                            len: chunk.len() as u16,
+                                            offset: param.offset,
+                                            value,
+                                        },
+                                    })
+                                ));
                             }
                         } else {
                             for descriptor in characteristic.descriptors.iter_mut() {
@@ -247,6 +345,133 @@ impl Profile {
                     }
                 }
             }
+            esp_gatts_cb_event_t_ESP_GATTS_WRITE_EVT => {
+                let param = unsafe { (*param).write };
+
+                let characteristic = self
+                    .services
+                    .iter_mut()
+                    .flat_map(|service| service.characteristics.iter_mut())
+                    .find(|characteristic| characteristic.attribute_handle == Some(param.handle));
+
+                if let Some(characteristic) = characteristic {
+                    let incoming =
+                        unsafe { std::slice::from_raw_parts(param.value, param.len as usize) }
+                            .to_vec();
+
+                    if param.is_prep {
+                        info!(
+                            "Buffering {} bytes for long write of characteristic {} at offset {}.",
+                            incoming.len(),
+                            characteristic,
+                            param.offset
+                        );
+                        characteristic.buffer_prepared_write(param.offset as usize, &incoming);
+
+                        if param.need_rsp {
+                            // The Prepare-Write Response must echo back the handle, offset and
+                            // value of this fragment, or a spec-compliant client (checking the
+                            // echo to detect a corrupted queue) will cancel the whole long write.
+                            let mut value = [0u8; 600];
+                            value[..incoming.len()].copy_from_slice(&incoming);
+
+                            unsafe {
+                                esp_nofail!(esp_ble_gatts_send_response(
+                                    gatts_if,
+                                    param.conn_id,
+                                    param.trans_id,
+                                    esp_gatt_status_t_ESP_GATT_OK,
+                                    leaky_box_raw!(esp_gatt_rsp_t {
+                                        attr_value: esp_gatt_value_t {
+                                            auth_req: esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE as _,
+                                            handle: param.handle,
+                                            len: incoming.len() as u16,
+                                            offset: param.offset,
+                                            value,
+                                        },
+                                    })
+                                ));
+                            }
+                        }
+                    } else {
+                        info!("Received write event for characteristic {}.", characteristic);
+                        characteristic.write_value(incoming);
+
+                        if param.need_rsp {
+                            unsafe {
+                                esp_nofail!(esp_ble_gatts_send_response(
+                                    gatts_if,
+                                    param.conn_id,
+                                    param.trans_id,
+                                    esp_gatt_status_t_ESP_GATT_OK,
+                                    std::ptr::null_mut(),
+                                ));
+                            }
+                        }
+                    }
+                } else if let Some(characteristic) = self
+                    .services
+                    .iter_mut()
+                    .flat_map(|service| service.characteristics.iter_mut())
+                    .find(|characteristic| {
+                        characteristic.descriptors.iter().any(|descriptor| {
+                            descriptor.attribute_handle == Some(param.handle)
+                                && descriptor.uuid == BleUuid::CLIENT_CHARACTERISTIC_CONFIGURATION
+                        })
+                    })
+                {
+                    let incoming =
+                        unsafe { std::slice::from_raw_parts(param.value, param.len as usize) };
+                    characteristic.set_subscription(param.conn_id, incoming);
+
+                    if param.need_rsp {
+                        unsafe {
+                            esp_nofail!(esp_ble_gatts_send_response(
+                                gatts_if,
+                                param.conn_id,
+                                param.trans_id,
+                                esp_gatt_status_t_ESP_GATT_OK,
+                                std::ptr::null_mut(),
+                            ));
+                        }
+                    }
+                } else {
+                    warn!(
+                        "Received write event for unknown attribute handle 0x{:04x}.",
+                        param.handle
+                    );
+                }
+            }
+            esp_gatts_cb_event_t_ESP_GATTS_EXEC_WRITE_EVT => {
+                let param = unsafe { (*param).exec_write };
+
+                let characteristics = self
+                    .services
+                    .iter_mut()
+                    .flat_map(|service| service.characteristics.iter_mut());
+
+                if param.exec_write_flag == ESP_GATT_PREP_WRITE_EXEC as u8 {
+                    debug!("Committing long write.");
+                    characteristics.for_each(|characteristic| {
+                        characteristic.commit_prepared_write()
+                    });
+                } else {
+                    debug!("Cancelling long write.");
+                    characteristics.for_each(|characteristic| {
+                        characteristic.cancel_prepared_write()
+                    });
+                }
+
+                unsafe {
+                    esp_nofail!(esp_ble_gatts_send_response(
+                        gatts_if,
+                        param.conn_id,
+                        param.trans_id,
+                        esp_gatt_status_t_ESP_GATT_OK,
+                        std::ptr::null_mut(),
+                    ));
+                }
+            }
             _ => {
                 warn!("Unhandled GATT server event: {:?}", event);
             }
@@ -0,0 +1,29 @@
+use crate::gatt_server::GattServer;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A thread-safe handle to a [`GattServer`] running on its dedicated FreeRTOS
+/// task (see [`GattServer::start`]).
+///
+/// Clone it freely and hand it to other tasks; [`lock`](Self::lock) gives
+/// exclusive access to the server for as long as the guard is held, the same
+/// way the Bluedroid callback itself does internally. The server's profiles,
+/// services, characteristics and descriptors are crate-private and have no
+/// public accessor on [`GattServer`].
+#[derive(Debug, Clone)]
+pub struct GattServerHandle(pub(crate) Arc<Mutex<GattServer>>);
+
+impl GattServerHandle {
+    pub(crate) fn new(server: GattServer) -> Self {
+        GattServerHandle(Arc::new(Mutex::new(server)))
+    }
+
+    /// Locks the server for exclusive access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned, i.e. a previous holder panicked while
+    /// holding it (including the Bluedroid callback itself).
+    pub fn lock(&self) -> MutexGuard<'_, GattServer> {
+        self.0.lock().expect("GattServer mutex was poisoned.")
+    }
+}
@@ -0,0 +1,97 @@
+//! The GATT server: profiles, services, characteristics and descriptors.
+
+pub mod advertisement;
+pub mod builder;
+pub mod characteristic;
+pub mod descriptor;
+mod gatts_event_handler;
+pub mod handle;
+pub mod profile;
+pub mod service;
+pub mod task;
+
+use advertisement::Advertisement;
+use esp_idf_sys::{esp_ble_adv_params_t, esp_ble_gap_start_advertising, esp_ble_gap_stop_advertising, esp_nofail};
+use profile::Profile;
+
+use crate::leaky_box_raw;
+
+/// The GATT server.
+///
+/// Owns the [`Profile`]s it serves and the state needed to answer Bluedroid's
+/// GATT server callback (`gatts_event_handler`).
+#[derive(Debug, Clone)]
+pub struct GattServer {
+    pub(crate) profiles: Vec<Profile>,
+    pub(crate) advertisement_parameters: esp_ble_adv_params_t,
+    /// The user-configured advertisement, if [`advertise`](Self::advertise) was
+    /// called. `None` keeps Bluedroid's previous hard-coded fallback behaviour.
+    pub(crate) advertisement: Option<Advertisement>,
+    pub(crate) name_set: bool,
+    /// The MTU negotiated with the currently connected client, in bytes.
+    ///
+    /// Defaults to the minimum ATT MTU (23 bytes) until `ESP_GATTS_MTU_EVT` is received.
+    pub(crate) mtu: u16,
+    /// Connection IDs of currently connected clients.
+    pub(crate) connections: Vec<u16>,
+}
+
+impl GattServer {
+    /// Creates a new, empty [`GattServer`].
+    ///
+    /// Advertising defaults to a 0x20-0x40 (20-40 ms) interval even before
+    /// [`advertise`](Self::advertise) is called: bindgen's all-zero `Default`
+    /// for `esp_ble_adv_params_t` leaves `adv_int_min`/`_max` at 0, below
+    /// Bluedroid's minimum of 0x0020, which would make
+    /// [`start_advertising`](Self::start_advertising) fail.
+    pub fn new() -> Self {
+        GattServer {
+            profiles: Vec::new(),
+            advertisement_parameters: esp_ble_adv_params_t {
+                adv_int_min: 0x20,
+                adv_int_max: 0x40,
+                ..Default::default()
+            },
+            advertisement: None,
+            name_set: false,
+            mtu: 23,
+            connections: Vec::new(),
+        }
+    }
+
+    /// Adds a [`Profile`] to the [`GattServer`].
+    pub fn profile(&mut self, profile: Profile) -> &mut Self {
+        self.profiles.push(profile);
+        self
+    }
+
+    /// Configures the device name, advertised payloads and advertising
+    /// parameters used by [`start_advertising`](Self::start_advertising).
+    pub fn advertise(&mut self, advertisement: Advertisement) -> &mut Self {
+        self.advertisement_parameters = advertisement.parameters;
+        self.advertisement = Some(advertisement);
+        self
+    }
+
+    /// Starts advertising using the currently configured parameters.
+    pub fn start_advertising(&mut self) {
+        unsafe {
+            esp_nofail!(esp_ble_gap_start_advertising(leaky_box_raw!(
+                self.advertisement_parameters
+            )));
+        }
+    }
+
+    /// Stops advertising.
+    pub fn stop_advertising(&mut self) {
+        unsafe {
+            esp_nofail!(esp_ble_gap_stop_advertising());
+        }
+    }
+}
+
+impl Default for GattServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,74 @@
+use crate::gatt_server::service::Service;
+use esp_idf_sys::{esp_ble_gatts_create_service, esp_gatt_if_t, esp_gatt_srvc_id_t, esp_nofail};
+use log::info;
+use std::fmt::Formatter;
+
+#[derive(Debug, Clone)]
+pub struct Profile {
+    name: Option<String>,
+    pub(crate) identifier: u16,
+    pub(crate) interface: Option<esp_gatt_if_t>,
+    pub(crate) services: Vec<Service>,
+}
+
+impl Profile {
+    /// Creates a new [`Profile`].
+    pub fn new(name: &str, identifier: u16) -> Profile {
+        Profile {
+            name: Some(String::from(name)),
+            identifier,
+            interface: None,
+            services: Vec::new(),
+        }
+    }
+
+    /// Adds a [`Service`] to the [`Profile`].
+    pub fn add_service(&mut self, service: &mut Service) -> &mut Self {
+        self.services.push(service.clone());
+        self
+    }
+
+    /// Registers this profile's services with the Bluedroid stack.
+    ///
+    /// This function should be called on the event of the profile being registered.
+    pub(crate) fn register_services(&mut self) {
+        info!("Registering {}'s services.", &self);
+
+        let interface = self
+            .interface
+            .expect("Cannot register services for a profile without an interface.");
+
+        self.services.iter().for_each(|service| unsafe {
+            esp_nofail!(esp_ble_gatts_create_service(
+                interface,
+                &mut esp_gatt_srvc_id_t {
+                    id: service.uuid.into(),
+                    is_primary: service.is_primary,
+                } as *mut _,
+                // One handle for the service declaration itself, plus two per characteristic
+                // (value + declaration), one per descriptor and one per included service, is
+                // a safe upper bound.
+                (1 + service.characteristics.len() * 2
+                    + service
+                        .characteristics
+                        .iter()
+                        .map(|c| c.descriptors.len())
+                        .sum::<usize>()
+                    + service.included_services.len()) as u16,
+            ));
+        });
+    }
+}
+
+impl std::fmt::Display for Profile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (app_id {})",
+            self.name
+                .clone()
+                .unwrap_or_else(|| "Unnamed profile".to_string()),
+            self.identifier
+        )
+    }
+}
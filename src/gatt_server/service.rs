@@ -0,0 +1,129 @@
+use crate::gatt_server::characteristic::Characteristic;
+use crate::utilities::BleUuid;
+use esp_idf_sys::{esp_ble_gatts_add_included_service, esp_nofail};
+use log::info;
+use std::collections::HashMap;
+use std::fmt::Formatter;
+
+#[derive(Debug, Clone)]
+pub struct Service {
+    name: Option<String>,
+    pub(crate) uuid: BleUuid,
+    pub(crate) characteristics: Vec<Characteristic>,
+    pub(crate) handle: Option<u16>,
+    pub(crate) is_primary: bool,
+    /// UUIDs of other services, on the same profile, included into this one
+    /// via [`include`](Self::include).
+    ///
+    /// Stored by UUID rather than a snapshot of the [`Service`] itself, since at
+    /// the time [`include`](Self::include) is called the included service has not
+    /// been registered yet and has no `handle`. The live handle is looked up by
+    /// UUID from the owning profile's services once this service's `CREATE_EVT`
+    /// fires, in [`register_included_services`](Self::register_included_services).
+    pub(crate) included_services: Vec<BleUuid>,
+    /// Attribute handles Bluedroid assigned to each "include" declaration, filled
+    /// in as `ESP_GATTS_ADD_INCL_SRVC_EVT` arrives for each included service, in
+    /// the same order as [`included_services`](Self::included_services).
+    pub(crate) included_service_handles: Vec<u16>,
+}
+
+impl Service {
+    /// Creates a new, primary [`Service`].
+    pub fn new(name: &str, uuid: BleUuid) -> Service {
+        Service {
+            name: Some(String::from(name)),
+            uuid,
+            characteristics: Vec::new(),
+            handle: None,
+            is_primary: true,
+            included_services: Vec::new(),
+            included_service_handles: Vec::new(),
+        }
+    }
+
+    /// Adds a [`Characteristic`] to the [`Service`].
+    pub fn add_characteristic(&mut self, characteristic: &mut Characteristic) -> &mut Self {
+        self.characteristics.push(characteristic.clone());
+        self
+    }
+
+    /// Marks this service as secondary.
+    ///
+    /// Secondary services are not independently advertised or discovered as
+    /// top-level services; they only exist to be included into a primary
+    /// service via [`include`](Self::include).
+    pub fn secondary(mut self) -> Self {
+        self.is_primary = false;
+        self
+    }
+
+    /// Includes another [`Service`] inside this one, via
+    /// `esp_ble_gatts_add_included_service`.
+    ///
+    /// `other` must be declared on the same profile, before this service, so
+    /// that its attribute handle is already known by the time this service's
+    /// `CREATE_EVT` fires.
+    pub fn include(&mut self, other: &Service) -> &mut Self {
+        self.included_services.push(other.uuid);
+        self
+    }
+
+    /// Registers the characteristics of this [`Service`].
+    ///
+    /// This function should be called on the event of the service being created.
+    pub(crate) fn register_characteristics(&mut self) {
+        info!("Registering {}'s characteristics.", &self);
+        self.characteristics
+            .iter_mut()
+            .for_each(|characteristic: &mut Characteristic| {
+                characteristic.register_self(
+                    self.handle
+                        .expect("Cannot register a characteristic to a service without a handle."),
+                );
+            });
+    }
+
+    /// Registers this service's included services.
+    ///
+    /// `sibling_handles` maps every already-registered service on the same
+    /// profile, by UUID, to its attribute handle — the live handle for each
+    /// UUID in [`included_services`](Self::included_services) is looked up from
+    /// it, since [`include`](Self::include) only had the UUID to go on.
+    ///
+    /// This function should be called on the event of the service being created.
+    pub(crate) fn register_included_services(&mut self, sibling_handles: &HashMap<BleUuid, u16>) {
+        let service_handle = self
+            .handle
+            .expect("Cannot include services in a service without a handle.");
+
+        self.included_services.iter().for_each(|uuid| {
+            let included_handle = *sibling_handles.get(uuid).unwrap_or_else(|| {
+                panic!(
+                    "Included service {uuid} must be registered before the service that includes it."
+                )
+            });
+
+            info!("Including {} into {}.", uuid, self);
+
+            unsafe {
+                esp_nofail!(esp_ble_gatts_add_included_service(
+                    service_handle,
+                    included_handle
+                ));
+            }
+        });
+    }
+}
+
+impl std::fmt::Display for Service {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({})",
+            self.name
+                .clone()
+                .unwrap_or_else(|| "Unnamed service".to_string()),
+            self.uuid
+        )
+    }
+}
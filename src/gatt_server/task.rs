@@ -0,0 +1,145 @@
+//! Runs the GATT server's Bluedroid GATTS callback registration and app
+//! registration on its own FreeRTOS task, pinned to a chosen core.
+//!
+//! That task does not run the event loop itself: once registration is done it
+//! parks, and events are handled by the registered callback on whichever task
+//! Bluedroid invokes it from.
+//!
+//! This does *not* bring up the Bluetooth controller or the Bluedroid stack
+//! itself: the caller must have already called
+//! `esp_bt_controller_init`/`esp_bt_controller_enable` and
+//! `esp_bluedroid_init`/`esp_bluedroid_enable` (typically once, at startup)
+//! before calling [`GattServer::start`].
+
+use crate::gatt_server::handle::GattServerHandle;
+use crate::gatt_server::GattServer;
+use esp_idf_sys::{
+    esp_ble_gatts_app_register, esp_ble_gatts_cb_param_t, esp_ble_gatts_register_callback,
+    esp_gatt_if_t, esp_gatts_cb_event_t, esp_nofail, vTaskDelay, xTaskCreatePinnedToCore,
+};
+use std::ffi::c_void;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+
+/// Which CPU core the dedicated GATT task should be pinned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BleTaskCore {
+    Core0,
+    Core1,
+}
+
+impl From<BleTaskCore> for esp_idf_sys::BaseType_t {
+    fn from(core: BleTaskCore) -> Self {
+        match core {
+            BleTaskCore::Core0 => 0,
+            BleTaskCore::Core1 => 1,
+        }
+    }
+}
+
+/// The single running [`GattServer`], reached from the `extern "C"` callback
+/// Bluedroid invokes. Bluedroid's `esp_ble_gatts_register_callback` takes a bare
+/// function pointer with no user-data slot, so this has to be a global.
+static RUNNING_SERVER: OnceLock<GattServerHandle> = OnceLock::new();
+
+extern "C" fn gatts_event_handler_trampoline(
+    event: esp_gatts_cb_event_t,
+    gatts_if: esp_gatt_if_t,
+    param: *mut esp_ble_gatts_cb_param_t,
+) {
+    if let Some(handle) = RUNNING_SERVER.get() {
+        handle.lock().gatts_event_handler(event, gatts_if, param);
+    }
+}
+
+struct TaskArgs {
+    handle: GattServerHandle,
+    ready: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl GattServer {
+    /// Spawns Bluedroid GATTS callback/app registration on a dedicated task
+    /// pinned to `core`, and blocks the calling task until that task signals it
+    /// is ready (`esp_ble_gatts_app_register` called for every profile; this
+    /// does not wait for the `ESP_GATTS_REG_EVT`s that complete registration).
+    ///
+    /// The Bluetooth controller and Bluedroid stack itself must already be
+    /// initialized and enabled (`esp_bt_controller_init`/`_enable`,
+    /// `esp_bluedroid_init`/`_enable`) before calling this — see the module docs.
+    ///
+    /// Returns a [`GattServerHandle`] that can be cloned and shared with other
+    /// tasks, which can then safely call back into the server (e.g.
+    /// `handle.lock().start_advertising()`) alongside the Bluedroid callback,
+    /// which locks the same handle. `GattServer` has no public accessor for its
+    /// characteristics, so `set_value` cannot be reached through the handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called more than once per process.
+    pub fn start(self, core: BleTaskCore) -> GattServerHandle {
+        let handle = GattServerHandle::new(self);
+
+        RUNNING_SERVER
+            .set(handle.clone())
+            .ok()
+            .expect("GattServer::start must only be called once.");
+
+        let ready = Arc::new((Mutex::new(false), Condvar::new()));
+        let task_args = Box::new(TaskArgs {
+            handle: handle.clone(),
+            ready: ready.clone(),
+        });
+
+        unsafe {
+            xTaskCreatePinnedToCore(
+                Some(Self::gatt_task),
+                b"bluedroid_gatt\0".as_ptr() as *const _,
+                4096,
+                Box::into_raw(task_args) as *mut c_void,
+                5,
+                std::ptr::null_mut(),
+                core.into(),
+            );
+        }
+
+        let (is_ready, cvar) = &*ready;
+        let mut is_ready = is_ready.lock().unwrap();
+        while !*is_ready {
+            is_ready = cvar.wait(is_ready).unwrap();
+        }
+
+        handle
+    }
+
+    /// The body of the dedicated GATT task: registers the Bluedroid GATTS
+    /// callback and every configured profile, signals readiness, then parks
+    /// forever. This task dispatches no events itself: all event handling
+    /// happens inside `gatts_event_handler_trampoline`, on whichever task
+    /// Bluedroid invokes it from.
+    extern "C" fn gatt_task(args: *mut c_void) {
+        let TaskArgs { handle, ready } = *unsafe { Box::from_raw(args as *mut TaskArgs) };
+
+        unsafe {
+            esp_nofail!(esp_ble_gatts_register_callback(Some(
+                gatts_event_handler_trampoline
+            )));
+
+            handle
+                .lock()
+                .profiles
+                .iter()
+                .for_each(|profile| {
+                    esp_nofail!(esp_ble_gatts_app_register(profile.identifier));
+                });
+        }
+
+        let (is_ready, cvar) = &*ready;
+        *is_ready.lock().unwrap() = true;
+        cvar.notify_all();
+
+        loop {
+            unsafe {
+                vTaskDelay(esp_idf_sys::portMAX_DELAY);
+            }
+        }
+    }
+}
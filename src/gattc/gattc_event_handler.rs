@@ -0,0 +1,236 @@
+use crate::gattc::remote_characteristic::RemoteCharacteristic;
+use crate::gattc::remote_descriptor::RemoteDescriptor;
+use crate::gattc::remote_service::RemoteService;
+use crate::gattc::GattClient;
+use crate::utilities::{BleUuid, CharacteristicProperties};
+use esp_idf_sys::{
+    esp_ble_gattc_get_all_char, esp_ble_gattc_get_all_descr, esp_ble_gattc_open,
+    esp_ble_gattc_search_service, esp_gatt_if_t, esp_gatt_status_t_ESP_GATT_OK,
+    esp_gattc_cb_event_t, esp_gattc_cb_event_t_ESP_GATTC_CLOSE_EVT,
+    esp_gattc_cb_event_t_ESP_GATTC_CONNECT_EVT, esp_gattc_cb_event_t_ESP_GATTC_DISCONNECT_EVT,
+    esp_gattc_cb_event_t_ESP_GATTC_NOTIFY_EVT, esp_gattc_cb_event_t_ESP_GATTC_OPEN_EVT,
+    esp_gattc_cb_event_t_ESP_GATTC_READ_CHAR_EVT, esp_gattc_cb_event_t_ESP_GATTC_REG_EVT,
+    esp_gattc_cb_event_t_ESP_GATTC_SEARCH_CMPL_EVT, esp_gattc_cb_event_t_ESP_GATTC_SEARCH_RES_EVT,
+    esp_gattc_cb_event_t_ESP_GATTC_WRITE_CHAR_EVT,
+    esp_gattc_cb_event_t_ESP_GATTC_WRITE_DESCR_EVT, esp_gattc_cb_param_t, esp_gattc_char_elem_t,
+    esp_gattc_descr_elem_t,
+};
+use log::{debug, info, warn};
+
+impl GattClient {
+    /// The main GATT client event loop.
+    pub(crate) fn gattc_event_handler(
+        &mut self,
+        event: esp_gattc_cb_event_t,
+        gattc_if: esp_gatt_if_t,
+        param: *mut esp_gattc_cb_param_t,
+    ) {
+        #[allow(non_upper_case_globals)]
+        match event {
+            esp_gattc_cb_event_t_ESP_GATTC_REG_EVT => {
+                let param = unsafe { (*param).reg };
+                if param.app_id == self.app_id {
+                    info!("GATT client app {} registered.", self.app_id);
+                    self.interface = Some(gattc_if);
+                }
+            }
+            esp_gattc_cb_event_t_ESP_GATTC_OPEN_EVT | esp_gattc_cb_event_t_ESP_GATTC_CONNECT_EVT => {
+                let conn_id = unsafe { (*param).open.conn_id };
+                info!("Connected to remote device (conn_id {}).", conn_id);
+                self.conn_id = Some(conn_id);
+                self.discovery_buffer.clear();
+
+                unsafe {
+                    esp_ble_gattc_search_service(gattc_if, conn_id, std::ptr::null_mut());
+                }
+            }
+            esp_gattc_cb_event_t_ESP_GATTC_SEARCH_RES_EVT => {
+                let param = unsafe { (*param).search_res };
+                let uuid = BleUuid::from(param.srvc_id.uuid);
+                debug!(
+                    "Discovered remote service {} (handles 0x{:04x}-0x{:04x}).",
+                    uuid, param.start_handle, param.end_handle
+                );
+                self.discovery_buffer.push(RemoteService::new(
+                    uuid,
+                    param.start_handle,
+                    param.end_handle,
+                ));
+            }
+            esp_gattc_cb_event_t_ESP_GATTC_SEARCH_CMPL_EVT => {
+                let param = unsafe { (*param).search_cmpl };
+                if param.status != esp_gatt_status_t_ESP_GATT_OK {
+                    warn!("Remote service discovery failed.");
+                    return;
+                }
+
+                info!(
+                    "Remote service discovery complete, discovering characteristics and descriptors."
+                );
+
+                let conn_id = param.conn_id;
+                let mut services = std::mem::take(&mut self.discovery_buffer);
+
+                for service in services.iter_mut() {
+                    Self::discover_characteristics(gattc_if, conn_id, service);
+                }
+
+                self.services = services;
+
+                if let Some(callback) = self.on_discovered.take() {
+                    callback(self);
+                }
+            }
+            esp_gattc_cb_event_t_ESP_GATTC_READ_CHAR_EVT => {
+                let param = unsafe { (*param).read };
+                if let Some(callback) = self.pending_reads.remove(&param.handle) {
+                    let value = unsafe {
+                        std::slice::from_raw_parts(param.value, param.value_len as usize)
+                    }
+                    .to_vec();
+                    callback(value);
+                }
+            }
+            esp_gattc_cb_event_t_ESP_GATTC_WRITE_CHAR_EVT => {
+                let param = unsafe { (*param).write };
+                if let Some(callback) = self.pending_writes.remove(&param.handle) {
+                    callback();
+                }
+            }
+            esp_gattc_cb_event_t_ESP_GATTC_WRITE_DESCR_EVT => {
+                let param = unsafe { (*param).write };
+                if let Some(callback) = self.pending_subscriptions.remove(&param.handle) {
+                    callback();
+                }
+            }
+            esp_gattc_cb_event_t_ESP_GATTC_NOTIFY_EVT => {
+                let param = unsafe { (*param).notify };
+                let value = unsafe {
+                    std::slice::from_raw_parts(param.value, param.value_len as usize)
+                }
+                .to_vec();
+                info!(
+                    "Received {} of {} bytes for handle 0x{:04x}.",
+                    if param.is_notify { "notification" } else { "indication" },
+                    value.len(),
+                    param.handle
+                );
+
+                let callback = self
+                    .services
+                    .iter()
+                    .flat_map(|service| service.characteristics.iter())
+                    .find(|characteristic| characteristic.handle == param.handle)
+                    .and_then(|characteristic| characteristic.on_notify.clone());
+
+                if let Some(callback) = callback {
+                    let mut callback = callback.lock().unwrap();
+                    (callback)(param.handle, value);
+                }
+            }
+            esp_gattc_cb_event_t_ESP_GATTC_DISCONNECT_EVT
+            | esp_gattc_cb_event_t_ESP_GATTC_CLOSE_EVT => {
+                info!("Disconnected from remote device.");
+                self.conn_id = None;
+                self.services.clear();
+            }
+            _ => {
+                warn!("Unhandled GATT client event: {:?}", event);
+            }
+        }
+    }
+
+    /// Walks a discovered service's characteristics, and for each of those, its
+    /// descriptors, following the `retrieveDescriptors` pattern from the ESP-IDF
+    /// examples: call `esp_ble_gattc_get_all_descr` with an incrementing offset
+    /// until it reports `ESP_GATT_INVALID_OFFSET`.
+    fn discover_characteristics(gattc_if: esp_gatt_if_t, conn_id: u16, service: &mut RemoteService) {
+        let mut offset = 0u16;
+
+        loop {
+            let mut char_elem = esp_gattc_char_elem_t::default();
+            let mut count = 1u16;
+
+            let status = unsafe {
+                esp_ble_gattc_get_all_char(
+                    gattc_if,
+                    conn_id,
+                    service.start_handle,
+                    service.end_handle,
+                    &mut char_elem,
+                    &mut count,
+                    offset,
+                )
+            };
+
+            if status != esp_gatt_status_t_ESP_GATT_OK as i32 || count == 0 {
+                break;
+            }
+
+            let mut characteristic = RemoteCharacteristic::new(
+                BleUuid::from(char_elem.uuid),
+                char_elem.char_handle,
+                CharacteristicProperties::from(char_elem.properties),
+            );
+
+            Self::discover_descriptors(gattc_if, conn_id, &mut characteristic);
+            service.characteristics.push(characteristic);
+
+            offset += 1;
+        }
+    }
+
+    fn discover_descriptors(
+        gattc_if: esp_gatt_if_t,
+        conn_id: u16,
+        characteristic: &mut RemoteCharacteristic,
+    ) {
+        let mut offset = 0u16;
+
+        loop {
+            let mut descr_elem = esp_gattc_descr_elem_t::default();
+            let mut count = 1u16;
+
+            let status = unsafe {
+                esp_ble_gattc_get_all_descr(
+                    gattc_if,
+                    conn_id,
+                    characteristic.handle,
+                    &mut descr_elem,
+                    &mut count,
+                    offset,
+                )
+            };
+
+            // Past the last descriptor, the status is `ESP_GATT_INVALID_OFFSET`.
+            if status != esp_gatt_status_t_ESP_GATT_OK as i32 || count == 0 {
+                break;
+            }
+
+            characteristic
+                .descriptors
+                .push(RemoteDescriptor::new(BleUuid::from(descr_elem.uuid), descr_elem.handle));
+
+            offset += 1;
+        }
+    }
+
+    /// Connects to a remote device by its Bluetooth address.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this [`GattClient`] has not been [`register`](Self::register)ed
+    /// yet, or if `ESP_GATTC_REG_EVT` has not arrived for it yet.
+    pub fn connect(&self, remote_bda: [u8; 6]) {
+        unsafe {
+            esp_ble_gattc_open(
+                self.interface.expect(
+                    "GattClient has not been registered yet (call GattClient::register first)."
+                ),
+                remote_bda.as_ptr() as *mut _,
+                esp_idf_sys::esp_ble_addr_type_t_BLE_ADDR_TYPE_PUBLIC,
+                true,
+            );
+        }
+    }
+}
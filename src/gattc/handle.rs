@@ -0,0 +1,28 @@
+use crate::gattc::GattClient;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A thread-safe handle to a [`GattClient`] that has been [`register`](GattClient::register)ed
+/// with the Bluedroid stack.
+///
+/// Clone it freely and hand it to other tasks; [`lock`](Self::lock) gives
+/// exclusive access to the client (and, through it, to its discovered
+/// services, characteristics and descriptors) for as long as the guard is
+/// held, the same way the Bluedroid callback itself does internally.
+#[derive(Clone)]
+pub struct GattClientHandle(pub(crate) Arc<Mutex<GattClient>>);
+
+impl GattClientHandle {
+    pub(crate) fn new(client: GattClient) -> Self {
+        GattClientHandle(Arc::new(Mutex::new(client)))
+    }
+
+    /// Locks the client for exclusive access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned, i.e. a previous holder panicked while
+    /// holding it (including the Bluedroid callback itself).
+    pub fn lock(&self) -> MutexGuard<'_, GattClient> {
+        self.0.lock().expect("GattClient mutex was poisoned.")
+    }
+}
@@ -0,0 +1,265 @@
+//! The GATT client (central) role: connecting to remote GATT servers and
+//! discovering their services, characteristics and descriptors.
+
+mod gattc_event_handler;
+pub mod handle;
+pub mod remote_characteristic;
+pub mod remote_descriptor;
+pub mod remote_service;
+
+use crate::gattc::handle::GattClientHandle;
+use crate::gattc::remote_characteristic::RemoteCharacteristic;
+use crate::gattc::remote_service::RemoteService;
+use esp_idf_sys::{
+    esp_ble_gattc_app_register, esp_ble_gattc_cb_param_t, esp_ble_gattc_register_callback,
+    esp_gatt_if_t, esp_gattc_cb_event_t, esp_gattc_cb_event_t_ESP_GATTC_REG_EVT, esp_nofail,
+};
+use std::collections::HashMap;
+use std::sync::{Mutex, Once, OnceLock};
+
+/// Every [`GattClient`] that has been [`register`](GattClient::register)ed,
+/// keyed by application identifier.
+///
+/// Bluedroid's `esp_ble_gattc_register_callback` takes a single bare function
+/// pointer shared by every GATTC app, with no user-data slot, so this has to
+/// be a global the trampoline can dispatch through — the same `OnceLock`
+/// trampoline pattern `gatt_server::task` uses for the server side.
+static CLIENTS: OnceLock<Mutex<HashMap<u16, GattClientHandle>>> = OnceLock::new();
+static CALLBACK_REGISTERED: Once = Once::new();
+
+fn clients() -> &'static Mutex<HashMap<u16, GattClientHandle>> {
+    CLIENTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+extern "C" fn gattc_event_handler_trampoline(
+    event: esp_gattc_cb_event_t,
+    gattc_if: esp_gatt_if_t,
+    param: *mut esp_ble_gattc_cb_param_t,
+) {
+    let clients = clients().lock().expect("GATT client registry mutex was poisoned.");
+
+    #[allow(non_upper_case_globals)]
+    if event == esp_gattc_cb_event_t_ESP_GATTC_REG_EVT {
+        // Not yet assigned an interface: every registering client must see this
+        // to find out whether it is the one Bluedroid just registered.
+        clients
+            .values()
+            .for_each(|handle| handle.lock().gattc_event_handler(event, gattc_if, param));
+    } else if let Some(handle) = clients
+        .values()
+        .find(|handle| handle.lock().interface == Some(gattc_if))
+    {
+        handle.lock().gattc_event_handler(event, gattc_if, param);
+    }
+}
+
+type ReadCallback = Box<dyn FnOnce(Vec<u8>) + Send>;
+type WriteCallback = Box<dyn FnOnce() + Send>;
+type DiscoveryCallback = Box<dyn FnOnce(&mut GattClient) + Send>;
+
+/// A GATT client, i.e. the central/master side of a BLE connection.
+///
+/// Mirrors [`GattServer`](crate::gatt_server::GattServer) for the peripheral
+/// role: register one per application identifier, connect it to a remote
+/// device, and it will walk the remote GATT database for you.
+pub struct GattClient {
+    pub(crate) app_id: u16,
+    pub(crate) interface: Option<esp_gatt_if_t>,
+    pub(crate) conn_id: Option<u16>,
+    pub(crate) services: Vec<RemoteService>,
+    pub(crate) on_discovered: Option<DiscoveryCallback>,
+    pending_reads: HashMap<u16, ReadCallback>,
+    pending_writes: HashMap<u16, WriteCallback>,
+    pending_subscriptions: HashMap<u16, WriteCallback>,
+    /// Services found so far by `ESP_GATTC_SEARCH_RES_EVT`, before
+    /// `ESP_GATTC_SEARCH_CMPL_EVT` triggers characteristic/descriptor discovery.
+    discovery_buffer: Vec<RemoteService>,
+}
+
+impl GattClient {
+    /// Creates a new [`GattClient`] for the given application identifier.
+    pub fn new(app_id: u16) -> Self {
+        GattClient {
+            app_id,
+            interface: None,
+            conn_id: None,
+            services: Vec::new(),
+            on_discovered: None,
+            pending_reads: HashMap::new(),
+            pending_writes: HashMap::new(),
+            pending_subscriptions: HashMap::new(),
+            discovery_buffer: Vec::new(),
+        }
+    }
+
+    /// Registers this [`GattClient`] with the Bluedroid stack: installs the
+    /// shared GATTC callback (once, no matter how many clients are registered)
+    /// and calls `esp_ble_gattc_app_register` for this client's application
+    /// identifier.
+    ///
+    /// Returns a [`GattClientHandle`] that can be cloned and shared with other
+    /// tasks; use it to reach this client once `ESP_GATTC_REG_EVT` has set its
+    /// interface, e.g. to call [`connect`](Self::connect).
+    pub fn register(self) -> GattClientHandle {
+        let app_id = self.app_id;
+        let handle = GattClientHandle::new(self);
+
+        CALLBACK_REGISTERED.call_once(|| unsafe {
+            esp_nofail!(esp_ble_gattc_register_callback(Some(
+                gattc_event_handler_trampoline
+            )));
+        });
+
+        clients()
+            .lock()
+            .expect("GATT client registry mutex was poisoned.")
+            .insert(app_id, handle.clone());
+
+        unsafe {
+            esp_nofail!(esp_ble_gattc_app_register(app_id));
+        }
+
+        handle
+    }
+
+    /// Registers a callback invoked once service/characteristic/descriptor
+    /// discovery has finished after a successful connection.
+    pub fn on_discovered(&mut self, callback: impl FnOnce(&mut GattClient) + Send + 'static) -> &mut Self {
+        self.on_discovered = Some(Box::new(callback));
+        self
+    }
+
+    /// The services discovered on the currently (or most recently) connected peer.
+    pub fn services(&self) -> &[RemoteService] {
+        &self.services
+    }
+
+    /// Finds a discovered service by UUID.
+    pub fn service(&self, uuid: crate::utilities::BleUuid) -> Option<&RemoteService> {
+        self.services.iter().find(|service| service.uuid == uuid)
+    }
+
+    pub(crate) fn read_by_handle(
+        &mut self,
+        conn_id: u16,
+        handle: u16,
+        callback: impl FnOnce(Vec<u8>) + Send + 'static,
+    ) {
+        use esp_idf_sys::{esp_ble_gattc_read_char, esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE, esp_nofail};
+
+        self.pending_reads.insert(handle, Box::new(callback));
+
+        unsafe {
+            esp_nofail!(esp_ble_gattc_read_char(
+                self.interface.expect("GattClient has not been registered yet."),
+                conn_id,
+                handle,
+                esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE,
+            ));
+        }
+    }
+
+    pub(crate) fn write_by_handle(
+        &mut self,
+        conn_id: u16,
+        handle: u16,
+        mut value: Vec<u8>,
+        with_response: bool,
+        callback: impl FnOnce() + Send + 'static,
+    ) {
+        use esp_idf_sys::{
+            esp_ble_gattc_write_char, esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE,
+            esp_gatt_write_type_t_ESP_GATT_WRITE_TYPE_NO_RSP,
+            esp_gatt_write_type_t_ESP_GATT_WRITE_TYPE_RSP, esp_nofail,
+        };
+
+        self.pending_writes.insert(handle, Box::new(callback));
+
+        let write_type = if with_response {
+            esp_gatt_write_type_t_ESP_GATT_WRITE_TYPE_RSP
+        } else {
+            esp_gatt_write_type_t_ESP_GATT_WRITE_TYPE_NO_RSP
+        };
+
+        unsafe {
+            esp_nofail!(esp_ble_gattc_write_char(
+                self.interface.expect("GattClient has not been registered yet."),
+                conn_id,
+                handle,
+                value.len() as u16,
+                value.as_mut_ptr(),
+                write_type,
+                esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE,
+            ));
+        }
+    }
+
+    pub(crate) fn set_subscription(
+        &mut self,
+        conn_id: u16,
+        characteristic: &RemoteCharacteristic,
+        enable: bool,
+        indicate: bool,
+        callback: impl FnOnce() + Send + 'static,
+    ) {
+        use crate::utilities::BleUuid;
+        use esp_idf_sys::{
+            esp_ble_gattc_register_for_notify, esp_ble_gattc_unregister_for_notify, esp_nofail,
+        };
+
+        let interface = self.interface.expect("GattClient has not been registered yet.");
+
+        unsafe {
+            if enable {
+                esp_nofail!(esp_ble_gattc_register_for_notify(
+                    interface,
+                    std::ptr::null_mut(),
+                    characteristic.handle,
+                ));
+            } else {
+                esp_nofail!(esp_ble_gattc_unregister_for_notify(
+                    interface,
+                    std::ptr::null_mut(),
+                    characteristic.handle,
+                ));
+            }
+        }
+
+        let cccd = characteristic
+            .descriptor(BleUuid::CLIENT_CHARACTERISTIC_CONFIGURATION)
+            .expect("Characteristic has no Client Characteristic Configuration Descriptor.");
+
+        let flags: u16 = if !enable {
+            0x0000
+        } else if indicate {
+            0x0002
+        } else {
+            0x0001
+        };
+
+        self.pending_subscriptions
+            .insert(cccd.handle, Box::new(callback));
+        self.write_cccd(conn_id, cccd.handle, flags);
+    }
+
+    fn write_cccd(&self, conn_id: u16, handle: u16, flags: u16) {
+        use esp_idf_sys::{
+            esp_ble_gattc_write_char_descr, esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE,
+            esp_gatt_write_type_t_ESP_GATT_WRITE_TYPE_RSP, esp_nofail,
+        };
+
+        let mut value = flags.to_le_bytes();
+
+        unsafe {
+            esp_nofail!(esp_ble_gattc_write_char_descr(
+                self.interface.expect("GattClient has not been registered yet."),
+                conn_id,
+                handle,
+                value.len() as u16,
+                value.as_mut_ptr(),
+                esp_gatt_write_type_t_ESP_GATT_WRITE_TYPE_RSP,
+                esp_gatt_auth_req_t_ESP_GATT_AUTH_REQ_NONE,
+            ));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::gattc::remote_descriptor::RemoteDescriptor;
+use crate::gattc::GattClient;
+use crate::utilities::{BleUuid, CharacteristicProperties};
+use std::fmt::Formatter;
+use std::sync::{Arc, Mutex};
+
+/// A user-supplied callback invoked whenever a notification or indication
+/// arrives for a [`RemoteCharacteristic`], with the attribute handle and the
+/// received value.
+type OnNotifyCallback = dyn FnMut(u16, Vec<u8>) + Send;
+
+/// A characteristic discovered on a remote GATT server, including its descriptors.
+#[derive(Clone)]
+pub struct RemoteCharacteristic {
+    pub(crate) uuid: BleUuid,
+    pub(crate) handle: u16,
+    pub(crate) properties: CharacteristicProperties,
+    pub(crate) descriptors: Vec<RemoteDescriptor>,
+    pub(crate) on_notify: Option<Arc<Mutex<OnNotifyCallback>>>,
+}
+
+impl RemoteCharacteristic {
+    pub(crate) fn new(uuid: BleUuid, handle: u16, properties: CharacteristicProperties) -> Self {
+        RemoteCharacteristic {
+            uuid,
+            handle,
+            properties,
+            descriptors: Vec::new(),
+            on_notify: None,
+        }
+    }
+
+    /// The characteristic's UUID.
+    pub fn uuid(&self) -> BleUuid {
+        self.uuid
+    }
+
+    /// The characteristic's value attribute handle on the remote server.
+    pub fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    /// The descriptors discovered under this characteristic (e.g. its CCCD).
+    pub fn descriptors(&self) -> &[RemoteDescriptor] {
+        &self.descriptors
+    }
+
+    /// Finds a descriptor by UUID among this characteristic's discovered descriptors.
+    pub fn descriptor(&self, uuid: BleUuid) -> Option<&RemoteDescriptor> {
+        self.descriptors.iter().find(|descriptor| descriptor.uuid == uuid)
+    }
+
+    /// Reads the characteristic's current value from the remote server.
+    ///
+    /// `callback` is invoked once with the value when `ESP_GATTC_READ_CHAR_EVT`
+    /// arrives for this handle.
+    pub fn read(
+        &self,
+        client: &mut GattClient,
+        conn_id: u16,
+        callback: impl FnOnce(Vec<u8>) + Send + 'static,
+    ) {
+        client.read_by_handle(conn_id, self.handle, callback);
+    }
+
+    /// Writes `value` to the characteristic, with or without response.
+    pub fn write(
+        &self,
+        client: &mut GattClient,
+        conn_id: u16,
+        value: Vec<u8>,
+        with_response: bool,
+        callback: impl FnOnce() + Send + 'static,
+    ) {
+        client.write_by_handle(conn_id, self.handle, value, with_response, callback);
+    }
+
+    /// Enables or disables notifications/indications on this characteristic by
+    /// writing its CCCD, registering/unregistering for the underlying
+    /// `esp_ble_gattc_register_for_notify` plumbing along the way.
+    pub fn subscribe(
+        &self,
+        client: &mut GattClient,
+        conn_id: u16,
+        enable: bool,
+        indicate: bool,
+        callback: impl FnOnce() + Send + 'static,
+    ) {
+        client.set_subscription(conn_id, self, enable, indicate, callback);
+    }
+
+    /// Registers a callback invoked with `(handle, value)` for every
+    /// notification or indication received for this characteristic, once
+    /// [`subscribe`](Self::subscribe) has enabled them.
+    pub fn on_notify(&mut self, callback: impl FnMut(u16, Vec<u8>) + Send + 'static) -> &mut Self {
+        self.on_notify = Some(Arc::new(Mutex::new(callback)));
+        self
+    }
+}
+
+impl std::fmt::Debug for RemoteCharacteristic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteCharacteristic")
+            .field("uuid", &self.uuid)
+            .field("handle", &self.handle)
+            .field("properties", &self.properties)
+            .field("descriptors", &self.descriptors)
+            .field("on_notify", &self.on_notify.as_ref().map(|_| "Fn"))
+            .finish()
+    }
+}
+
+impl std::fmt::Display for RemoteCharacteristic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "remote characteristic {} (handle 0x{:04x})", self.uuid, self.handle)
+    }
+}
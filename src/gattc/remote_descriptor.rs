@@ -0,0 +1,31 @@
+use crate::utilities::BleUuid;
+use std::fmt::Formatter;
+
+/// A descriptor discovered on a remote GATT server.
+#[derive(Debug, Clone)]
+pub struct RemoteDescriptor {
+    pub(crate) uuid: BleUuid,
+    pub(crate) handle: u16,
+}
+
+impl RemoteDescriptor {
+    pub(crate) fn new(uuid: BleUuid, handle: u16) -> Self {
+        RemoteDescriptor { uuid, handle }
+    }
+
+    /// The descriptor's UUID.
+    pub fn uuid(&self) -> BleUuid {
+        self.uuid
+    }
+
+    /// The descriptor's attribute handle on the remote server.
+    pub fn handle(&self) -> u16 {
+        self.handle
+    }
+}
+
+impl std::fmt::Display for RemoteDescriptor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "remote descriptor {} (handle 0x{:04x})", self.uuid, self.handle)
+    }
+}
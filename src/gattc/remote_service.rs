@@ -0,0 +1,50 @@
+use crate::gattc::remote_characteristic::RemoteCharacteristic;
+use crate::utilities::BleUuid;
+use std::fmt::Formatter;
+
+/// A service discovered on a remote GATT server, including its characteristics.
+#[derive(Debug, Clone)]
+pub struct RemoteService {
+    pub(crate) uuid: BleUuid,
+    pub(crate) start_handle: u16,
+    pub(crate) end_handle: u16,
+    pub(crate) characteristics: Vec<RemoteCharacteristic>,
+}
+
+impl RemoteService {
+    pub(crate) fn new(uuid: BleUuid, start_handle: u16, end_handle: u16) -> Self {
+        RemoteService {
+            uuid,
+            start_handle,
+            end_handle,
+            characteristics: Vec::new(),
+        }
+    }
+
+    /// The service's UUID.
+    pub fn uuid(&self) -> BleUuid {
+        self.uuid
+    }
+
+    /// The characteristics discovered under this service.
+    pub fn characteristics(&self) -> &[RemoteCharacteristic] {
+        &self.characteristics
+    }
+
+    /// Finds a characteristic by UUID among this service's discovered characteristics.
+    pub fn characteristic(&self, uuid: BleUuid) -> Option<&RemoteCharacteristic> {
+        self.characteristics
+            .iter()
+            .find(|characteristic| characteristic.uuid == uuid)
+    }
+}
+
+impl std::fmt::Display for RemoteService {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "remote service {} (handles 0x{:04x}-0x{:04x})",
+            self.uuid, self.start_handle, self.end_handle
+        )
+    }
+}
@@ -0,0 +1,19 @@
+//! A Rust wrapper around the ESP-IDF Bluedroid Bluetooth stack, focused on
+//! building GATT servers (and, increasingly, GATT clients) on the ESP32 family.
+
+pub mod gatt_server;
+pub mod gattc;
+pub mod utilities;
+
+/// Leaks a value onto the heap and returns a raw pointer to it.
+///
+/// Several Bluedroid APIs store a raw pointer to data they expect to outlive the
+/// call (advertisement data, attribute values, ...). Since we cannot generally
+/// prove how long Bluedroid will hold on to these pointers, we intentionally
+/// leak the backing allocation rather than risk a use-after-free.
+#[macro_export]
+macro_rules! leaky_box_raw {
+    ($x:expr) => {
+        Box::into_raw(Box::new($x))
+    };
+}
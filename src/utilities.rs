@@ -0,0 +1,167 @@
+//! Small value types shared across the GATT server (and client) implementation.
+
+use esp_idf_sys::{
+    esp_attr_control_t, esp_attr_control_t_ESP_GATT_AUTO_RSP,
+    esp_attr_control_t_ESP_GATT_RSP_BY_APP, esp_bt_uuid_t, esp_gatt_char_prop_t,
+};
+use std::fmt::{Display, Formatter};
+
+/// A Bluetooth UUID, in any of its three standard widths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BleUuid {
+    Uuid16(u16),
+    Uuid32(u32),
+    Uuid128([u8; 16]),
+}
+
+impl BleUuid {
+    /// The Client Characteristic Configuration Descriptor UUID (0x2902), used by
+    /// clients to subscribe to notifications/indications on a characteristic.
+    pub const CLIENT_CHARACTERISTIC_CONFIGURATION: BleUuid = BleUuid::Uuid16(0x2902);
+
+    /// Creates a [`BleUuid`] from a 16-bit UUID.
+    pub const fn from_uuid16(uuid: u16) -> Self {
+        Self::Uuid16(uuid)
+    }
+
+    /// Creates a [`BleUuid`] from a 32-bit UUID.
+    pub const fn from_uuid32(uuid: u32) -> Self {
+        Self::Uuid32(uuid)
+    }
+
+    /// Creates a [`BleUuid`] from a 128-bit UUID.
+    pub const fn from_uuid128(uuid: [u8; 16]) -> Self {
+        Self::Uuid128(uuid)
+    }
+}
+
+impl Display for BleUuid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BleUuid::Uuid16(uuid) => write!(f, "{:#06x}", uuid),
+            BleUuid::Uuid32(uuid) => write!(f, "{:#010x}", uuid),
+            BleUuid::Uuid128(uuid) => write!(f, "{:02x?}", uuid),
+        }
+    }
+}
+
+impl From<BleUuid> for esp_bt_uuid_t {
+    fn from(uuid: BleUuid) -> Self {
+        match uuid {
+            BleUuid::Uuid16(uuid) => esp_bt_uuid_t {
+                len: 2,
+                uuid: esp_idf_sys::esp_bt_uuid_t__bindgen_ty_1 { uuid16: uuid },
+            },
+            BleUuid::Uuid32(uuid) => esp_bt_uuid_t {
+                len: 4,
+                uuid: esp_idf_sys::esp_bt_uuid_t__bindgen_ty_1 { uuid32: uuid },
+            },
+            BleUuid::Uuid128(uuid) => esp_bt_uuid_t {
+                len: 16,
+                uuid: esp_idf_sys::esp_bt_uuid_t__bindgen_ty_1 { uuid128: uuid },
+            },
+        }
+    }
+}
+
+impl From<esp_bt_uuid_t> for BleUuid {
+    fn from(uuid: esp_bt_uuid_t) -> Self {
+        unsafe {
+            match uuid.len {
+                2 => BleUuid::Uuid16(uuid.uuid.uuid16),
+                4 => BleUuid::Uuid32(uuid.uuid.uuid32),
+                16 => BleUuid::Uuid128(uuid.uuid.uuid128),
+                _ => panic!("Invalid UUID length {}.", uuid.len),
+            }
+        }
+    }
+}
+
+/// Read/write permissions of a GATT attribute, as understood by Bluedroid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributePermissions {
+    Read,
+    ReadEncrypted,
+    ReadAuthenticated,
+    Write,
+    WriteEncrypted,
+    WriteAuthenticated,
+    ReadWrite,
+}
+
+impl From<AttributePermissions> for esp_idf_sys::esp_gatt_perm_t {
+    fn from(permissions: AttributePermissions) -> Self {
+        use esp_idf_sys::{
+            ESP_GATT_PERM_READ, ESP_GATT_PERM_READ_ENCRYPTED, ESP_GATT_PERM_READ_ENC_MITM,
+            ESP_GATT_PERM_WRITE, ESP_GATT_PERM_WRITE_ENCRYPTED, ESP_GATT_PERM_WRITE_ENC_MITM,
+        };
+
+        match permissions {
+            AttributePermissions::Read => ESP_GATT_PERM_READ,
+            AttributePermissions::ReadEncrypted => ESP_GATT_PERM_READ_ENCRYPTED,
+            AttributePermissions::ReadAuthenticated => ESP_GATT_PERM_READ_ENC_MITM,
+            AttributePermissions::Write => ESP_GATT_PERM_WRITE,
+            AttributePermissions::WriteEncrypted => ESP_GATT_PERM_WRITE_ENCRYPTED,
+            AttributePermissions::WriteAuthenticated => ESP_GATT_PERM_WRITE_ENC_MITM,
+            AttributePermissions::ReadWrite => ESP_GATT_PERM_READ | ESP_GATT_PERM_WRITE,
+        }
+    }
+}
+
+/// Properties of a GATT characteristic, as understood by Bluedroid.
+///
+/// Several properties can be combined, e.g. `READ | NOTIFY`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CharacteristicProperties(u8);
+
+impl CharacteristicProperties {
+    pub const BROADCAST: Self = Self(1 << 0);
+    pub const READ: Self = Self(1 << 1);
+    pub const WRITE_WITHOUT_RESPONSE: Self = Self(1 << 2);
+    pub const WRITE: Self = Self(1 << 3);
+    pub const NOTIFY: Self = Self(1 << 4);
+    pub const INDICATE: Self = Self(1 << 5);
+
+    /// Returns `true` if `self` has every flag set that `other` has.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for CharacteristicProperties {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl From<CharacteristicProperties> for esp_gatt_char_prop_t {
+    fn from(properties: CharacteristicProperties) -> Self {
+        properties.0
+    }
+}
+
+impl From<esp_gatt_char_prop_t> for CharacteristicProperties {
+    fn from(properties: esp_gatt_char_prop_t) -> Self {
+        CharacteristicProperties(properties)
+    }
+}
+
+/// Determines whether Bluedroid automatically answers read requests with the
+/// attribute's stored value, or whether the application is given a chance to
+/// compute the response itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeControl {
+    AutomaticResponse,
+    ResponseByApp,
+}
+
+impl From<AttributeControl> for esp_attr_control_t {
+    fn from(control: AttributeControl) -> Self {
+        match control {
+            AttributeControl::AutomaticResponse => esp_attr_control_t_ESP_GATT_AUTO_RSP as _,
+            AttributeControl::ResponseByApp => esp_attr_control_t_ESP_GATT_RSP_BY_APP as _,
+        }
+    }
+}